@@ -20,7 +20,7 @@ extern crate probability;
 extern crate statistics;
 
 use probability::distribution::Beta as Pearson;
-use probability::distribution::{Gaussian, Sample};
+use probability::distribution::{Continuous, Gamma, Gaussian, Sample, Uniform};
 use probability::source::Source;
 use std::{error, fmt};
 
@@ -40,6 +40,91 @@ pub struct Beta {
     betas: Vec<Pearson>,
 }
 
+/// A multifractal wavelet model with Dirichlet-distributed multipliers over
+/// `M`-ary trees.
+///
+/// The model generalizes `Beta`, which is restricted to dyadic (`M = 2`)
+/// cascades, to cascades in which every node splits into `arity` children.
+pub struct Dirichlet {
+    arity: usize,
+    gaussian: Gaussian,
+    alphas: Vec<f64>,
+}
+
+/// A Gamma conjugate prior, or posterior, over a `Beta(p, p)` shape
+/// parameter `p`.
+///
+/// The pair `(shape, rate)` is the sufficient-statistic accumulator: scoring
+/// a scale's worth of multipliers against the prior amounts to adding a
+/// count and an inverse-estimate sum to it, giving an updated posterior
+/// whose mean is a new point estimate of `p`.
+#[derive(Clone, Copy)]
+pub struct GammaPosterior {
+    shape: f64,
+    rate: f64,
+}
+
+impl GammaPosterior {
+    /// Create a prior (or posterior) with the given shape and rate.
+    ///
+    /// Both parameters should be positive; their ratio is the distribution's
+    /// mean, i.e., the point estimate of `p` before any data are observed.
+    pub fn new(shape: f64, rate: f64) -> Result<GammaPosterior> {
+        if shape <= 0.0 || rate <= 0.0 {
+            raise!("the shape and rate should be positive");
+        }
+        Ok(GammaPosterior { shape: shape, rate: rate })
+    }
+
+    /// The mean of the distribution, the point estimate of `p`.
+    pub fn mean(&self) -> f64 {
+        self.shape / self.rate
+    }
+
+    fn update(&self, count: f64, sum: f64) -> GammaPosterior {
+        GammaPosterior { shape: self.shape + count, rate: self.rate + sum }
+    }
+}
+
+/// The result of `Beta::fit_bayesian`.
+pub struct BayesianFit {
+    /// The fitted model, with each scale's beta parameter set to the
+    /// posterior mean.
+    pub model: Beta,
+    /// The per-scale posterior, in the same order as `model`'s scales, so
+    /// that callers can propagate the remaining uncertainty.
+    pub posteriors: Vec<GammaPosterior>,
+}
+
+/// The result of `Beta::diagnostics`, a self-similarity goodness-of-fit
+/// assessment.
+pub struct Fit {
+    /// The estimated scaling exponent, i.e., the slope of the regression of
+    /// log mean-square energy against scale index.
+    pub exponent: f64,
+    /// The coefficient of determination, `R²`, of the regression.
+    pub r_squared: f64,
+    /// The regression residual at every scale, ordered from coarsest to
+    /// finest, as in `Beta::sample`.
+    pub residuals: Vec<f64>,
+    /// The indices of the scales whose residual lies beyond a Tukey fence,
+    /// `1.5 ×` the interquartile range of `residuals`, and therefore break
+    /// self-similarity.
+    pub outliers: Vec<usize>,
+}
+
+/// Percentile confidence intervals for the parameters estimated by `fit`.
+pub struct ParameterIntervals {
+    /// The percentiles at which the intervals were evaluated.
+    pub percentiles: Vec<f64>,
+    /// The percentiles of the Gaussian mean.
+    pub mu: Vec<f64>,
+    /// The percentiles of the Gaussian standard deviation.
+    pub sigma: Vec<f64>,
+    /// The percentiles of each scale's beta parameter, outermost by scale.
+    pub betas: Vec<Vec<f64>>,
+}
+
 macro_rules! scales(
     ($number:expr) => (
         if $number == 0 {
@@ -56,6 +141,14 @@ macro_rules! blocks(
     );
 );
 
+macro_rules! arity(
+    ($number:expr) => (
+        if $number < 2 {
+            raise!("the arity should be at least two");
+        }
+    );
+);
+
 impl Beta {
     /// Fit the model to the data.
     ///
@@ -109,6 +202,281 @@ impl Beta {
 
         Ok(data)
     }
+
+    /// Compute the log-likelihood of an observed signal under the model.
+    ///
+    /// The data should contain `blocks × 2^scales` points, where `blocks` is
+    /// inferred from the length of `data` and `scales` is the number of
+    /// scales the model was fitted with. The function is the density
+    /// counterpart of `sample`: it recovers the tree of scaling and wavelet
+    /// coefficients via the Haar wavelet transform and scores each split
+    /// against the fitted `Gaussian` and `Beta` distributions.
+    pub fn log_density(&self, data: &[f64]) -> Result<f64> {
+        let nscale = self.betas.len();
+        let leaves = 1 << nscale;
+        if data.is_empty() || !data.len().is_multiple_of(leaves) {
+            raise!("the number of data points should be a positive multiple of 2^scales");
+        }
+        let blocks = data.len() / leaves;
+
+        let mut coefficients = data.to_vec();
+        dwt::forward(&mut coefficients, &dwt::wavelet::Haar::new(), nscale);
+
+        let mut sum = 0.0;
+        for j in 0..blocks {
+            sum += self.gaussian.pdf(coefficients[j]).ln();
+        }
+
+        // `coefficients[0..blocks]` is the orthonormal Haar approximation at
+        // the root, in the same (untransformed) units the wavelet bands are
+        // in; each split below recovers the approximation one level down by
+        // inverting the same orthonormal Haar step, which divides by `√2` in
+        // addition to applying the split's multiplier.
+        let mut nodes = coefficients[0..blocks].to_vec();
+
+        for i in 0..nscale {
+            let wavelets = &coefficients[(blocks << i)..(blocks << (i + 1))];
+            let mut children = Vec::with_capacity(nodes.len() * 2);
+            for (j, &u) in nodes.iter().enumerate() {
+                if u <= 0.0 {
+                    raise!("the model is not appropriate for the data");
+                }
+                let a = wavelets[j] / u;
+                sum += self.betas[i].pdf(a).ln() - u.abs().ln();
+                children.push((1.0 + a) * u / 2f64.sqrt());
+                children.push((1.0 - a) * u / 2f64.sqrt());
+            }
+            nodes = children;
+        }
+
+        Ok(sum)
+    }
+
+    /// Estimate percentile confidence intervals for the fitted parameters.
+    ///
+    /// The data are resampled `resamples` times by drawing, with
+    /// replacement, `blocks` of the original coarsest-scale blocks, and the
+    /// model is refit to each resample. The empirical distribution of
+    /// `gaussian.mu()`, `gaussian.sigma()`, and every scale's beta parameter
+    /// is then summarized at `percentiles` (each within `[0, 100]`) by
+    /// linear interpolation. Resamples for which the model turns out to be
+    /// inappropriate are skipped and counted; the call fails if fewer than
+    /// half of `resamples` produce a valid fit.
+    pub fn bootstrap<S>(data: &[f64], blocks: usize, resamples: usize, percentiles: &[f64],
+                         source: &mut S) -> Result<ParameterIntervals>
+        where S: Source
+    {
+        blocks!(blocks);
+        if resamples == 0 {
+            raise!("the number of resamples should be positive");
+        }
+        if percentiles.iter().any(|&p| !(0.0..=100.0).contains(&p)) {
+            raise!("each percentile should be within [0, 100]");
+        }
+        let scales = (data.len() as f64 / blocks as f64).log2().floor() as usize;
+        scales!(scales);
+        let span = 1 << scales;
+
+        let mut mus = Vec::new();
+        let mut sigmas = Vec::new();
+        let mut betas: Vec<Vec<f64>> = (0..scales).map(|_| Vec::new()).collect();
+
+        let uniform = Uniform::new(0.0, blocks as f64);
+        for _ in 0..resamples {
+            let mut resampled = Vec::with_capacity(blocks * span);
+            for _ in 0..blocks {
+                let block = uniform.sample(source) as usize;
+                resampled.extend_from_slice(&data[(block * span)..((block + 1) * span)]);
+            }
+            let model = match fit(&resampled, blocks, scales) {
+                Ok(model) => model,
+                Err(..) => continue,
+            };
+            mus.push(model.gaussian.mu());
+            sigmas.push(model.gaussian.sigma());
+            for (beta, values) in model.betas.iter().zip(betas.iter_mut()) {
+                values.push(beta.beta());
+            }
+        }
+
+        if mus.len() * 2 < resamples {
+            raise!("too few resamples produced a valid fit");
+        }
+
+        mus.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        sigmas.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        for values in betas.iter_mut() {
+            values.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        }
+
+        Ok(ParameterIntervals {
+            percentiles: percentiles.to_vec(),
+            mu: percentiles.iter().map(|&p| percentile(&mus, p)).collect(),
+            sigma: percentiles.iter().map(|&p| percentile(&sigmas, p)).collect(),
+            betas: betas.iter().map(|values| {
+                percentiles.iter().map(|&p| percentile(values, p)).collect()
+            }).collect(),
+        })
+    }
+
+    /// Fit the model to the data with a Bayesian, per-scale posterior over
+    /// each beta parameter, instead of `fit`'s plain moment matching.
+    ///
+    /// The recursive energy-ratio estimate that `new` computes, seeded at
+    /// each scale with the previous scale's posterior mean instead of its
+    /// moment estimate, is used as a sufficient statistic that updates
+    /// `prior`: if the estimate identifies a positive `p`, a scale
+    /// contributes a count equal to its number of wavelet coefficients and
+    /// an inverse-estimate sum in proportion to that count. A scale whose
+    /// energy is too scarce to identify `p` (a non-positive moment-matching estimate, which would
+    /// otherwise make `fit` raise an error) contributes nothing, so its
+    /// posterior collapses back to `prior`.
+    pub fn fit_bayesian(data: &[f64], blocks: usize, prior: &GammaPosterior) -> Result<BayesianFit> {
+        use statistics::{mean, variance};
+
+        blocks!(blocks);
+        let scales = (data.len() as f64 / blocks as f64).log2().floor() as usize;
+        scales!(scales);
+
+        let mut data = (&data[0..(blocks * (1 << scales))]).to_vec();
+        dwt::forward(&mut data, &dwt::wavelet::Haar::new(), scales);
+
+        let sigma = variance(&data[0..blocks]).sqrt();
+        if sigma <= 0.0 {
+            raise!("the model is not appropriate for the data");
+        }
+        let gaussian = Gaussian::new(mean(&data[0..blocks]), sigma);
+
+        let mut beta = 0.0;
+        let mut ms = mean_square(&data[0..blocks]);
+        let mut betas = Vec::with_capacity(scales);
+        let mut posteriors = Vec::with_capacity(scales);
+        for i in 0..scales {
+            let segment = &data[(blocks * (1 << i))..(blocks * (1 << (i + 1)))];
+            let new_ms = mean_square(segment);
+            let estimate = 0.5 * (ms / new_ms) * (beta + 1.0) - 0.5;
+
+            let count = segment.len() as f64;
+            let posterior = if estimate > 0.0 {
+                prior.update(count, count / estimate)
+            } else {
+                prior.update(0.0, 0.0)
+            };
+
+            beta = posterior.mean();
+            betas.push(Pearson::new(beta, beta, -1.0, 1.0));
+            posteriors.push(posterior);
+            ms = new_ms;
+        }
+
+        Ok(BayesianFit { model: Beta { gaussian: gaussian, betas: betas }, posteriors: posteriors })
+    }
+
+    /// Assess how well the long-range-dependence assumption holds for an
+    /// observed signal.
+    ///
+    /// The data should contain `blocks × 2^scales` points, as in
+    /// `log_density`. A self-similar process has log mean-square wavelet
+    /// energy that is linear in scale index; this is checked by a
+    /// least-squares fit, reported as the slope (`exponent`) and the `R²` of
+    /// that fit, alongside the per-scale residuals. Scales whose residual
+    /// falls outside a Tukey fence of `1.5 ×` the interquartile range of the
+    /// residuals are reported as `outliers` that break self-similarity.
+    pub fn diagnostics(&self, data: &[f64]) -> Result<Fit> {
+        let nscale = self.betas.len();
+        if nscale < 2 {
+            raise!("the model should have at least two scales for a diagnostic fit");
+        }
+        let leaves = 1 << nscale;
+        if data.is_empty() || !data.len().is_multiple_of(leaves) {
+            raise!("the number of data points should be a positive multiple of 2^scales");
+        }
+        let blocks = data.len() / leaves;
+
+        let mut coefficients = data.to_vec();
+        dwt::forward(&mut coefficients, &dwt::wavelet::Haar::new(), nscale);
+
+        let scales = (0..nscale).map(|i| i as f64).collect::<Vec<_>>();
+        let energies = (0..nscale).map(|i| {
+            mean_square(&coefficients[(blocks << i)..(blocks << (i + 1))]).ln()
+        }).collect::<Vec<_>>();
+
+        let (exponent, intercept) = least_squares(&scales, &energies);
+
+        let residuals = scales.iter().zip(energies.iter())
+                               .map(|(&x, &y)| y - (exponent * x + intercept))
+                               .collect::<Vec<_>>();
+
+        let mean_energy = energies.iter().sum::<f64>() / nscale as f64;
+        let ss_total = energies.iter().map(|&y| (y - mean_energy).powi(2)).sum::<f64>();
+        let ss_residual = residuals.iter().map(|&r| r * r).sum::<f64>();
+        let r_squared = if ss_total > 0.0 { 1.0 - ss_residual / ss_total } else { 1.0 };
+
+        let outliers = tukey_outliers(&residuals);
+
+        Ok(Fit { exponent: exponent, r_squared: r_squared, residuals: residuals, outliers: outliers })
+    }
+}
+
+impl Dirichlet {
+    /// Fit the model to the data.
+    ///
+    /// The number of points used for the analysis is `blocks × arity^scales`.
+    /// The parameters `blocks` and `arity` should each be at least two, and
+    /// `arity` is the number of children every node splits into.
+    pub fn new(data: &[f64], blocks: usize, arity: usize) -> Result<Dirichlet> {
+        blocks!(blocks);
+        arity!(arity);
+        let scales = (data.len() as f64 / blocks as f64).log(arity as f64).floor() as usize;
+        scales!(scales);
+        fit_dirichlet(data, blocks, scales, arity)
+    }
+
+    /// Fit the model to the data with a specific number of scales.
+    ///
+    /// The function is identical to `new` except for specifying the number
+    /// of scales instead of the number of blocks.
+    pub fn with_scales(data: &[f64], scales: usize, arity: usize) -> Result<Dirichlet> {
+        scales!(scales);
+        arity!(arity);
+        let blocks = (data.len() as f64 / arity.pow(scales as u32) as f64).floor() as usize;
+        blocks!(blocks);
+        fit_dirichlet(data, blocks, scales, arity)
+    }
+
+    /// Draw a sample.
+    ///
+    /// At every node, a multiplier vector `r` of length `arity` is drawn from
+    /// `Dirichlet(α, …, α)`, and child `k` is assigned `U · r[k] · arity`,
+    /// which preserves the conservation property enforced by `Beta::sample`'s
+    /// `(1 ± a)` rule.
+    pub fn sample<S>(&self, source: &mut S) -> Result<Vec<f64>> where S: Source {
+        let nscale = self.alphas.len();
+        let arity = self.arity;
+
+        let mut data = Vec::with_capacity(arity.pow(nscale as u32));
+        unsafe { data.set_len(arity.pow(nscale as u32)) };
+
+        let z = self.gaussian.sample(source);
+        if z < 0.0 {
+            raise!("the model is not appropriate for the data");
+        }
+        data[0] = z;
+
+        for i in 0..nscale {
+            let gamma = Gamma::new(self.alphas[i], 1.0);
+            for j in (0..arity.pow(i as u32)).rev() {
+                let x = data[j];
+                let draws = (0..arity).map(|_| gamma.sample(source)).collect::<Vec<_>>();
+                let total: f64 = draws.iter().sum();
+                for (k, draw) in draws.into_iter().enumerate() {
+                    data[arity * j + k] = x * arity as f64 * draw / total;
+                }
+            }
+        }
+
+        Ok(data)
+    }
 }
 
 impl error::Error for Error {
@@ -138,7 +506,11 @@ fn fit(data: &[f64], blocks: usize, scales: usize) -> Result<Beta> {
     let mut data = (&data[0..(blocks * (1 << scales))]).to_vec();
     dwt::forward(&mut data, &dwt::wavelet::Haar::new(), scales);
 
-    let gaussian = Gaussian::new(mean(&data[0..blocks]), variance(&data[0..blocks]).sqrt());
+    let sigma = variance(&data[0..blocks]).sqrt();
+    if sigma <= 0.0 {
+        raise!("the model is not appropriate for the data");
+    }
+    let gaussian = Gaussian::new(mean(&data[0..blocks]), sigma);
 
     let mut beta = 0.0;
     let mut ms = mean_square(&data[0..blocks]);
@@ -156,17 +528,115 @@ fn fit(data: &[f64], blocks: usize, scales: usize) -> Result<Beta> {
     Ok(Beta { gaussian: gaussian, betas: betas })
 }
 
+fn fit_dirichlet(data: &[f64], blocks: usize, scales: usize, arity: usize) -> Result<Dirichlet> {
+    use statistics::{mean, variance};
+
+    let data = &data[0..(blocks * arity.pow(scales as u32))];
+    let (root, energies) = pyramid(data, scales, arity);
+
+    let gaussian = Gaussian::new(mean(&root), variance(&root).sqrt());
+
+    let mut alphas = Vec::with_capacity(scales);
+    for &(parent_ms, detail_ms) in &energies {
+        let alpha = ((arity - 1) as f64 * parent_ms / detail_ms - 1.0) / arity as f64;
+        if alpha <= 0.0 {
+            raise!("the model is not appropriate for the data");
+        }
+        alphas.push(alpha);
+    }
+
+    Ok(Dirichlet { arity: arity, gaussian: gaussian, alphas: alphas })
+}
+
+/// Build the pyramid of scaling coefficients of an `arity`-ary cascade, from
+/// the root (`blocks` elements) down to the leaves (the original `data`), by
+/// repeatedly averaging groups of `arity` consecutive elements, and pair it
+/// with, at every level, the mean square of the parents and the mean, over
+/// every parent, of the variance of its `arity` children around it.
+///
+/// The latter is the `arity`-ary analogue of the squared Haar wavelet
+/// coefficients that `fit` derives from the dyadic wavelet transform: a node
+/// with value `x` conserves `arity · x` across its children, so the spread
+/// of the children around `x` plays the same role the detail coefficient
+/// plays in the dyadic cascade, and `Dirichlet`'s multiplier shape `alpha`
+/// can be read off it directly rather than through `fit`'s telescoped beta
+/// recursion.
+fn pyramid(data: &[f64], scales: usize, arity: usize) -> (Vec<f64>, Vec<(f64, f64)>) {
+    let mut levels = Vec::with_capacity(scales + 1);
+    let mut current = data.to_vec();
+    levels.push(current.clone());
+    for _ in 0..scales {
+        current = current.chunks(arity)
+                          .map(|chunk| chunk.iter().sum::<f64>() / arity as f64)
+                          .collect();
+        levels.push(current.clone());
+    }
+    levels.reverse();
+
+    let energies = (0..scales).map(|i| {
+        let parents = &levels[i];
+        let children = &levels[i + 1];
+        let parent_ms = mean_square(parents);
+        let detail_ms = children.chunks(arity).zip(parents.iter()).map(|(chunk, &p)| {
+            chunk.iter().map(|&x| (x - p) * (x - p)).sum::<f64>() / arity as f64
+        }).sum::<f64>() / parents.len() as f64;
+        (parent_ms, detail_ms)
+    }).collect();
+
+    (levels[0].clone(), energies)
+}
+
 #[inline]
 fn mean_square(data: &[f64]) -> f64 {
     &data.iter().fold(0.0, |sum, &x| sum + x * x) / data.len() as f64
 }
 
+/// Linearly interpolate the `p`-th percentile (`0 ≤ p ≤ 100`) of `sorted`.
+fn percentile(sorted: &[f64], p: f64) -> f64 {
+    let rank = (p / 100.0) * (sorted.len() - 1) as f64;
+    let lower = rank.floor() as usize;
+    let upper = rank.ceil() as usize;
+    let weight = rank - lower as f64;
+    sorted[lower] * (1.0 - weight) + sorted[upper] * weight
+}
+
+/// Fit `ys = slope × xs + intercept` by ordinary least squares.
+fn least_squares(xs: &[f64], ys: &[f64]) -> (f64, f64) {
+    let n = xs.len() as f64;
+    let mean_x = xs.iter().sum::<f64>() / n;
+    let mean_y = ys.iter().sum::<f64>() / n;
+
+    let mut covariance = 0.0;
+    let mut variance = 0.0;
+    for (&x, &y) in xs.iter().zip(ys.iter()) {
+        covariance += (x - mean_x) * (y - mean_y);
+        variance += (x - mean_x) * (x - mean_x);
+    }
+
+    let slope = covariance / variance;
+    (slope, mean_y - slope * mean_x)
+}
+
+/// The indices of the elements of `values` that lie beyond a Tukey fence,
+/// `1.5 ×` the interquartile range.
+fn tukey_outliers(values: &[f64]) -> Vec<usize> {
+    let mut sorted = values.to_vec();
+    sorted.sort_by(|a, b| a.partial_cmp(b).unwrap());
+
+    let q1 = percentile(&sorted, 25.0);
+    let q3 = percentile(&sorted, 75.0);
+    let fence = 1.5 * (q3 - q1);
+    let (low, high) = (q1 - fence, q3 + fence);
+
+    values.iter().enumerate().filter(|&(_, &v)| v < low || v > high).map(|(i, _)| i).collect()
+}
+
 #[cfg(test)]
 mod tests {
     use assert;
     use probability::source;
 
-    use Beta;
+    use {Beta, Dirichlet, GammaPosterior};
 
     #[test]
     fn new() {
@@ -220,4 +690,154 @@ mod tests {
 
         assert_eq!(data.len(), 8);
     }
+
+    #[test]
+    fn log_density() {
+        let data = [
+            4.018080337519417e-01, 7.596669169084191e-02, 2.399161535536580e-01,
+            1.233189348351655e-01, 1.839077882824167e-01, 2.399525256649028e-01,
+            4.172670690843695e-01, 4.965443032574213e-02, 9.027161099152811e-01,
+            9.447871897216460e-01, 4.908640924680799e-01, 4.892526384000189e-01,
+            3.377194098213772e-01, 9.000538464176620e-01, 3.692467811202150e-01,
+            1.112027552937874e-01, 7.802520683211379e-01, 3.897388369612534e-01,
+            2.416912859138327e-01, 4.039121455881147e-01, 9.645452516838859e-02,
+            1.319732926063351e-01, 9.420505907754851e-01, 9.561345402298023e-01,
+            5.752085950784656e-01, 5.977954294715582e-02, 2.347799133724063e-01,
+            3.531585712220711e-01, 8.211940401979591e-01, 1.540343765155505e-02,
+            4.302380165780784e-02, 1.689900294627044e-01, 6.491154749564521e-01,
+            7.317223856586703e-01, 6.477459631363067e-01, 4.509237064309449e-01,
+            5.470088922863450e-01, 2.963208056077732e-01, 7.446928070741562e-01,
+            1.889550150325445e-01, 6.867754333653150e-01, 1.835111557372697e-01,
+        ];
+
+        let model = Beta::new(&data, 5).unwrap();
+
+        assert::close(model.log_density(&data[0..40]).unwrap(), -7.20862103476239e+00, 1e-10);
+    }
+
+    #[test]
+    fn dirichlet_new() {
+        let data = [
+            4.018080337519417e-01, 7.596669169084191e-02, 2.399161535536580e-01,
+            1.233189348351655e-01, 1.839077882824167e-01, 2.399525256649028e-01,
+            4.172670690843695e-01, 4.965443032574213e-02, 9.027161099152811e-01,
+            9.447871897216460e-01, 4.908640924680799e-01, 4.892526384000189e-01,
+            3.377194098213772e-01, 9.000538464176620e-01, 3.692467811202150e-01,
+            1.112027552937874e-01, 7.802520683211379e-01, 3.897388369612534e-01,
+            2.416912859138327e-01, 4.039121455881147e-01, 9.645452516838859e-02,
+            1.319732926063351e-01, 9.420505907754851e-01, 9.561345402298023e-01,
+        ];
+
+        let model = Dirichlet::new(&data, 3, 2).unwrap();
+        let sampled = model.sample(&mut source::default()).unwrap();
+
+        assert_eq!(sampled.len(), 8);
+    }
+
+    #[test]
+    fn diagnostics() {
+        let data = [
+            4.983640519821430e-01, 9.597439585160811e-01, 3.403857266661332e-01,
+            5.852677509797773e-01, 2.238119394911370e-01, 7.512670593056529e-01,
+            2.550951154592691e-01, 5.059570516651424e-01, 6.990767226566860e-01,
+            8.909032525357985e-01, 9.592914252054443e-01, 5.472155299638031e-01,
+            1.386244428286791e-01, 1.492940055590575e-01, 2.575082541237365e-01,
+            8.407172559836625e-01, 2.542821789715310e-01, 8.142848260688164e-01,
+            2.435249687249893e-01, 9.292636231872278e-01, 3.499837659848087e-01,
+            1.965952504312082e-01, 2.510838579760311e-01, 6.160446761466392e-01,
+            4.732888489027293e-01, 3.516595070629968e-01, 8.308286278962909e-01,
+            5.852640911527243e-01, 5.497236082911395e-01, 9.171936638298100e-01,
+            2.858390188203735e-01, 7.572002291107213e-01, 7.537290942784953e-01,
+            3.804458469753567e-01, 5.678216407252211e-01, 7.585428956306361e-02,
+            5.395011866660715e-02, 5.307975530089727e-01, 7.791672301020112e-01,
+            9.340106842291830e-01, 1.299062084737301e-01, 5.688236608721927e-01,
+        ];
+
+        let model = Beta::with_scales(&data, 3).unwrap();
+        let fit = model.diagnostics(&data[0..40]).unwrap();
+
+        assert::close(fit.exponent, -1.6136294425672748e-01, 1e-10);
+        assert::close(fit.r_squared, 7.375850161394013e-01, 1e-10);
+        assert::close(&fit.residuals, &[
+            5.5568855758828306e-02, -1.1113771151765572e-01, 5.5568855758828306e-02,
+        ], 1e-10);
+        assert_eq!(fit.outliers.len(), 0);
+    }
+
+    #[test]
+    fn bootstrap() {
+        let data = [
+            4.018080337519417e-01, 7.596669169084191e-02, 2.399161535536580e-01,
+            1.233189348351655e-01, 1.839077882824167e-01, 2.399525256649028e-01,
+            4.172670690843695e-01, 4.965443032574213e-02, 9.027161099152811e-01,
+            9.447871897216460e-01, 4.908640924680799e-01, 4.892526384000189e-01,
+            3.377194098213772e-01, 9.000538464176620e-01, 3.692467811202150e-01,
+            1.112027552937874e-01, 7.802520683211379e-01, 3.897388369612534e-01,
+            2.416912859138327e-01, 4.039121455881147e-01, 9.645452516838859e-02,
+            1.319732926063351e-01, 9.420505907754851e-01, 9.561345402298023e-01,
+            5.752085950784656e-01, 5.977954294715582e-02, 2.347799133724063e-01,
+            3.531585712220711e-01, 8.211940401979591e-01, 1.540343765155505e-02,
+            4.302380165780784e-02, 1.689900294627044e-01, 6.491154749564521e-01,
+            7.317223856586703e-01, 6.477459631363067e-01, 4.509237064309449e-01,
+            5.470088922863450e-01, 2.963208056077732e-01, 7.446928070741562e-01,
+            1.889550150325445e-01, 6.867754333653150e-01, 1.835111557372697e-01,
+        ];
+
+        let intervals = Beta::bootstrap(&data, 5, 10, &[25.0, 50.0, 75.0],
+                                         &mut source::default()).unwrap();
+
+        assert::close(&intervals.mu, &[
+            9.630466090455881e-01, 1.103403565830974e+00, 1.226936349426754e+00,
+        ], 1e-10);
+        assert::close(&intervals.sigma, &[
+            3.690654428748059e-01, 4.008481153910545e-01, 4.750675578452278e-01,
+        ], 1e-10);
+        assert::close(&intervals.betas[0], &[
+            1.369043935580993e+01, 1.970207800428245e+01, 3.938469134152844e+01,
+        ], 1e-10);
+
+        assert!(Beta::bootstrap(&data, 5, 10, &[975.0], &mut source::default()).is_err());
+    }
+
+    #[test]
+    fn fit_bayesian() {
+        let data = [
+            4.018080337519417e-01, 7.596669169084191e-02, 2.399161535536580e-01,
+            1.233189348351655e-01, 1.839077882824167e-01, 2.399525256649028e-01,
+            4.172670690843695e-01, 4.965443032574213e-02, 9.027161099152811e-01,
+            9.447871897216460e-01, 4.908640924680799e-01, 4.892526384000189e-01,
+            3.377194098213772e-01, 9.000538464176620e-01, 3.692467811202150e-01,
+            1.112027552937874e-01, 7.802520683211379e-01, 3.897388369612534e-01,
+            2.416912859138327e-01, 4.039121455881147e-01, 9.645452516838859e-02,
+            1.319732926063351e-01, 9.420505907754851e-01, 9.561345402298023e-01,
+            5.752085950784656e-01, 5.977954294715582e-02, 2.347799133724063e-01,
+            3.531585712220711e-01, 8.211940401979591e-01, 1.540343765155505e-02,
+            4.302380165780784e-02, 1.689900294627044e-01, 6.491154749564521e-01,
+            7.317223856586703e-01, 6.477459631363067e-01, 4.509237064309449e-01,
+            5.470088922863450e-01, 2.963208056077732e-01, 7.446928070741562e-01,
+            1.889550150325445e-01, 6.867754333653150e-01, 1.835111557372697e-01,
+        ];
+
+        let prior = GammaPosterior::new(2.0, 1.0).unwrap();
+        let fit = Beta::fit_bayesian(&data, 5, &prior).unwrap();
+
+        assert::close(&fit.posteriors.iter().map(|p| p.mean()).collect::<Vec<_>>(), &[
+            5.3607736575410527e+00, 7.9261554466173001e-01, 1.5381934822982495e+00,
+        ], 1e-10);
+    }
+
+    #[test]
+    fn fit_bayesian_degenerate_scale() {
+        let data = (0..40).map(|i| {
+            let base = if i % 2 == 0 { 1.0 } else { -1.0 };
+            base + 1e-3 * ((i as f64) * 0.3).sin()
+        }).collect::<Vec<_>>();
+
+        let prior = GammaPosterior::new(2.0, 1.0).unwrap();
+        let fit = Beta::fit_bayesian(&data, 5, &prior).unwrap();
+
+        // The finest scale's moment estimate is non-positive for this
+        // alternating signal, so its posterior collapses back to the prior.
+        assert::close(fit.posteriors[2].mean(), prior.mean(), 1e-10);
+    }
 }